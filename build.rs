@@ -0,0 +1,17 @@
+use std::process::Command;
+
+/// Captures the `rustc` version used for this build so `load_plugin` can
+/// detect a compiler mismatch between host and plugin before ever touching
+/// the plugin's vtable. Cargo re-runs `build.rs` (and recompiles this crate)
+/// whenever the toolchain changes, so this always reflects the compiler
+/// actually used for the current build.
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    println!("cargo:rustc-env=PLUGIN_INTERFACE_RUSTC_VERSION={}", version);
+}
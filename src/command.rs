@@ -0,0 +1,15 @@
+/// Describes a single invokable command a plugin exposes, beyond its raw Lua
+/// functions.
+///
+/// Mirrors the `command()`/`help()`/`execute()` surface servers plugins
+/// expose, so a host can build an auto-generated command listing or `/help`
+/// output without every plugin reinventing its own introspection.
+#[derive(Debug, Clone)]
+pub struct CommandDesc {
+    /// Name the command is invoked by, e.g. `"reload"`.
+    pub name: String,
+    /// Short, one-line description shown in a command listing.
+    pub help: String,
+    /// Usage string shown alongside `help`, e.g. `"reload <plugin>"`.
+    pub usage: String,
+}
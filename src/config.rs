@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Configuration payload handed to a plugin at load time.
+///
+/// Mirrors the Geyser plugin interface's `config_file` convention: a plugin is
+/// typically configured via a sibling file next to its dynamic library (e.g.
+/// `myplugin.toml` next to `myplugin.so`), but callers that already have the
+/// parsed value on hand can pass it directly instead of round-tripping through
+/// disk.
+#[derive(Debug, Clone)]
+pub enum PluginConfig {
+    /// No configuration was supplied for this load.
+    None,
+    /// Path to a config file that sits alongside the plugin library.
+    Path(PathBuf),
+    /// An already-parsed TOML value.
+    Toml(toml::Value),
+    /// An already-parsed JSON value.
+    Json(serde_json::Value),
+}
+
+impl PluginConfig {
+    /// Builds a config pointing at a sibling file next to `plugin_path`.
+    ///
+    /// # Parameters
+    /// - `plugin_path`: Path to the plugin's dynamic library.
+    /// - `config_file`: File name of the config file, resolved relative to
+    ///   `plugin_path`'s parent directory.
+    pub fn sibling(plugin_path: &Path, config_file: &str) -> Self {
+        let path = plugin_path
+            .parent()
+            .map(|dir| dir.join(config_file))
+            .unwrap_or_else(|| PathBuf::from(config_file));
+        PluginConfig::Path(path)
+    }
+
+    /// Reads and parses the config as TOML.
+    ///
+    /// # Returns
+    /// - `Ok(Some(value))` if this config resolves to a TOML value.
+    /// - `Ok(None)` if this config is a JSON value instead, or `None`.
+    /// - `Err` if the file could not be read or parsed.
+    pub fn as_toml(&self) -> Result<Option<toml::Value>, Box<dyn std::error::Error>> {
+        match self {
+            PluginConfig::Path(path) => {
+                let contents = fs::read_to_string(path)?;
+                Ok(Some(toml::from_str(&contents)?))
+            }
+            PluginConfig::Toml(value) => Ok(Some(value.clone())),
+            PluginConfig::Json(_) | PluginConfig::None => Ok(None),
+        }
+    }
+
+    /// Reads and parses the config as JSON.
+    ///
+    /// # Returns
+    /// - `Ok(Some(value))` if this config resolves to a JSON value.
+    /// - `Ok(None)` if this config is a TOML value instead, or `None`.
+    /// - `Err` if the file could not be read or parsed.
+    pub fn as_json(&self) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error>> {
+        match self {
+            PluginConfig::Path(path) => {
+                let contents = fs::read_to_string(path)?;
+                Ok(Some(serde_json::from_str(&contents)?))
+            }
+            PluginConfig::Json(value) => Ok(Some(value.clone())),
+            PluginConfig::Toml(_) | PluginConfig::None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sibling_resolves_relative_to_plugin_dir() {
+        let config = PluginConfig::sibling(Path::new("/plugins/myplugin.so"), "myplugin.toml");
+        assert!(matches!(
+            config,
+            PluginConfig::Path(path) if path == Path::new("/plugins/myplugin.toml")
+        ));
+    }
+
+    #[test]
+    fn sibling_falls_back_to_bare_name_without_a_parent_dir() {
+        let config = PluginConfig::sibling(Path::new("myplugin.so"), "myplugin.toml");
+        assert!(matches!(
+            config,
+            PluginConfig::Path(path) if path == Path::new("myplugin.toml")
+        ));
+    }
+
+    #[test]
+    fn as_toml_reads_and_parses_a_path() {
+        let path = std::env::temp_dir().join("cygnixy_plugin_config_test_as_toml.toml");
+        fs::write(&path, "key = \"value\"").unwrap();
+
+        let config = PluginConfig::Path(path.clone());
+        let value = config.as_toml().unwrap().unwrap();
+        assert_eq!(value["key"].as_str(), Some("value"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn as_toml_returns_none_for_a_json_config() {
+        let config = PluginConfig::Json(serde_json::json!({"key": "value"}));
+        assert!(config.as_toml().unwrap().is_none());
+    }
+
+    #[test]
+    fn as_json_reads_and_parses_a_path() {
+        let path = std::env::temp_dir().join("cygnixy_plugin_config_test_as_json.json");
+        fs::write(&path, r#"{"key": "value"}"#).unwrap();
+
+        let config = PluginConfig::Path(path.clone());
+        let value = config.as_json().unwrap().unwrap();
+        assert_eq!(value["key"], "value");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn as_json_returns_none_for_a_toml_config() {
+        let config = PluginConfig::Toml(toml::Value::String("value".to_string()));
+        assert!(config.as_json().unwrap().is_none());
+    }
+
+    #[test]
+    fn none_config_has_no_toml_or_json() {
+        assert!(PluginConfig::None.as_toml().unwrap().is_none());
+        assert!(PluginConfig::None.as_json().unwrap().is_none());
+    }
+}
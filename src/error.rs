@@ -0,0 +1,51 @@
+use thiserror::Error;
+
+/// Errors returned by [`crate::PluginManager`].
+///
+/// Replaces the previous opaque `Box<dyn Error>` returns so hosts can match
+/// on a specific failure (a missing dependency, say) instead of
+/// string-matching an error message.
+#[derive(Debug, Error)]
+pub enum PluginError {
+    /// No plugin with this name is currently loaded.
+    #[error("plugin '{0}' not found")]
+    NotFound(String),
+
+    /// A plugin with this name is already loaded.
+    #[error("plugin '{0}' is already loaded")]
+    AlreadyLoaded(String),
+
+    /// `plugin` declares a dependency on `dependency`, which isn't loaded.
+    #[error("plugin '{plugin}' depends on '{dependency}', which is not loaded")]
+    DependencyMissing { plugin: String, dependency: String },
+
+    /// `plugin` can't be unloaded because `dependents` still depend on it.
+    #[error("plugin '{plugin}' cannot be unloaded: still depended on by {dependents:?}")]
+    InUseBy {
+        plugin: String,
+        dependents: Vec<String>,
+    },
+
+    /// A required exported symbol was missing from a plugin's library.
+    #[error("required symbol '{0}' missing from plugin library")]
+    SymbolMissing(String),
+
+    /// The plugin at `path` was built against an incompatible interface or
+    /// `rustc` version and was rejected before its vtable was dereferenced.
+    #[error("plugin '{0}' is ABI-incompatible with this host: {1}")]
+    AbiMismatch(String, String),
+
+    /// The plugin's dynamic library could not be loaded. (A required symbol
+    /// that can't be resolved is reported as `SymbolMissing`.)
+    #[error(transparent)]
+    Library(#[from] libloading::Error),
+
+    /// Registering or deregistering the plugin's Lua functions failed.
+    #[error(transparent)]
+    Lua(#[from] mlua::Error),
+
+    /// The plugin itself returned an error from `on_load`/`on_unload`, or a
+    /// file watcher couldn't be started, or a reload path isn't valid UTF-8.
+    #[error("plugin error: {0}")]
+    Plugin(#[from] Box<dyn std::error::Error>),
+}
@@ -1,8 +1,47 @@
+mod command;
+mod config;
+mod error;
+mod registrar;
+mod watch;
+
+pub use command::CommandDesc;
+pub use config::PluginConfig;
+pub use error::PluginError;
+pub use registrar::PluginRegistrar;
+
 use libloading::{Library, Symbol};
 use mlua::Lua;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tracing::{error, trace};
+use watch::PluginWatcher;
+
+/// ABI version of this crate's `PluginLua` interface, derived from its own
+/// `CARGO_PKG_VERSION_MAJOR`. Bump the major version in `Cargo.toml` whenever
+/// `PluginLua` or its calling convention changes in a way that would make an
+/// old plugin binary unsafe to load against a new host (or vice versa).
+/// `export_plugin!` exposes this via a `plugin_interface_version` symbol, and
+/// `load_plugin` refuses to load a plugin whose version doesn't match.
+pub const PLUGIN_INTERFACE_VERSION: u32 = const_parse_u32(env!("CARGO_PKG_VERSION_MAJOR"));
+
+/// `rustc` version used to compile this copy of the interface crate, captured
+/// by `build.rs`. Loading a plugin built with a different compiler is
+/// undefined behavior even when the interface version matches, so
+/// `load_plugin` checks this too before calling `create_plugin`.
+pub const RUSTC_VERSION: &str = env!("PLUGIN_INTERFACE_RUSTC_VERSION");
+
+const fn const_parse_u32(s: &str) -> u32 {
+    let bytes = s.as_bytes();
+    let mut value = 0u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as u32;
+        i += 1;
+    }
+    value
+}
 
 /// Trait that all Lua plugins must implement.
 ///
@@ -14,7 +53,11 @@ pub trait PluginLua: Send + Sync {
     /// Called when the plugin is loaded.
     ///
     /// This method is used for initializing resources or performing setup tasks.
-    fn on_load(&mut self) -> Result<(), Box<dyn Error>>;
+    /// `config` carries whatever configuration the host resolved for this
+    /// plugin (a sibling config file or an already-parsed value), letting the
+    /// plugin pick up endpoints, feature flags, and credentials without
+    /// hard-coding them.
+    fn on_load(&mut self, config: &PluginConfig) -> Result<(), Box<dyn Error>>;
 
     /// Called when the plugin is unloaded.
     ///
@@ -25,6 +68,57 @@ pub trait PluginLua: Send + Sync {
     ///
     /// Each function is associated with a name, allowing it to be called from Lua scripts.
     fn get_lua_functions(&self, lua: &Lua) -> HashMap<String, mlua::Function>;
+
+    /// Returns the names of other plugins that must be loaded before this one.
+    ///
+    /// `PluginManager::load_all` topologically sorts by these names and only
+    /// calls `on_load` once every dependency has already been loaded.
+    /// Defaults to no dependencies.
+    fn dependencies(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Returns the commands this plugin wants surfaced in a host's
+    /// help/dispatch system, beyond its raw Lua functions.
+    ///
+    /// Defaults to no commands; a plugin that has nothing to dispatch on
+    /// doesn't need to implement this.
+    fn commands(&self) -> Vec<CommandDesc> {
+        Vec::new()
+    }
+}
+
+/// A loaded plugin paired with the `Library` that owns its code.
+///
+/// Keeping the two together (rather than in separate collections) means a
+/// plugin can be dropped independently of every other loaded plugin, which is
+/// what makes hot-reloading and per-plugin unloading safe.
+struct LoadedPlugin {
+    plugin: Box<dyn PluginLua>,
+    /// Kept alive only to be dropped after `on_unload` runs; never accessed
+    /// directly. Shared (rather than uniquely owned) because a single
+    /// registrar library can back several plugins at once.
+    #[allow(dead_code)]
+    library: Option<Arc<Library>>,
+    /// Path the plugin was loaded from, if it came from a dynamic library.
+    path: Option<PathBuf>,
+    /// Lua namespace this plugin's functions are registered under, e.g.
+    /// `"net"` or the dotted `"net.http"`. Equal to `plugin.name()` unless
+    /// the plugin was registered via `PluginRegistrar` under a different name.
+    namespace: String,
+    /// Config the plugin was last loaded with, so a reload can reuse it.
+    config: PluginConfig,
+}
+
+/// A plugin already instantiated from its dynamic library by `load_all`,
+/// waiting on its declared dependencies before it's moved into
+/// `PluginManager::plugins`.
+struct PendingPlugin<'a> {
+    plugin: Box<dyn PluginLua>,
+    library: Library,
+    path: &'a str,
+    config_path: Option<&'a str>,
+    dependencies: Vec<String>,
 }
 
 /// Manages loading, unloading, and interacting with Lua plugins.
@@ -34,10 +128,18 @@ pub trait PluginLua: Send + Sync {
 /// - Managing plugin instances and ensuring they are correctly initialized and cleaned up.
 /// - Automatically registering Lua functions provided by plugins.
 pub struct PluginManager {
-    /// A map of plugin names to plugin instances.
-    plugins: HashMap<String, Box<dyn PluginLua>>,
-    /// Keeps track of loaded libraries to prevent premature unloading.
-    libraries: Vec<Library>,
+    /// A map of plugin names to their loaded plugin + owning library.
+    plugins: HashMap<String, LoadedPlugin>,
+    /// Active file watchers, keyed by plugin name, kept alive for as long as
+    /// the plugin should be watched for hot-reload.
+    watchers: HashMap<String, PluginWatcher>,
+    /// Plugin names queued for reload by a file watcher, drained by
+    /// `process_reloads`.
+    pending_reloads: Arc<Mutex<Vec<String>>>,
+    /// Set for the duration of a `load_registrar_plugin` call so that
+    /// `PluginRegistrar::register` can pair each plugin it registers with the
+    /// library currently calling in.
+    pending_library: Option<Arc<Library>>,
 }
 
 impl Default for PluginManager {
@@ -52,7 +154,9 @@ impl PluginManager {
     pub fn new() -> Self {
         PluginManager {
             plugins: HashMap::new(),
-            libraries: Vec::new(),
+            watchers: HashMap::new(),
+            pending_reloads: Arc::new(Mutex::new(Vec::new())),
+            pending_library: None,
         }
     }
 
@@ -60,6 +164,10 @@ impl PluginManager {
     ///
     /// # Parameters
     /// - `path`: Path to the dynamic library containing the plugin.
+    /// - `config_path`: Optional path to a sibling config file (e.g.
+    ///   `myplugin.toml` next to `myplugin.so`) that is handed to the plugin's
+    ///   `on_load` as a [`PluginConfig::Path`]. Pass `None` if the plugin
+    ///   doesn't need one.
     ///
     /// # Returns
     /// - `Ok(())` if the plugin is successfully loaded.
@@ -68,26 +176,384 @@ impl PluginManager {
     /// # Safety
     /// This method uses unsafe code to interact with the dynamic library and call the plugin's
     /// exported `create_plugin` function.
-    pub fn load_plugin(&mut self, path: &str) -> Result<(), Box<dyn Error>> {
+    pub fn load_plugin(
+        &mut self,
+        path: &str,
+        config_path: Option<&str>,
+    ) -> Result<(), PluginError> {
+        let config = match config_path {
+            Some(config_path) => PluginConfig::Path(PathBuf::from(config_path)),
+            None => PluginConfig::None,
+        };
+
+        let (mut plugin, library) = unsafe { Self::create_from_library(path)? };
+        if self.plugins.contains_key(plugin.name()) {
+            return Err(PluginError::AlreadyLoaded(plugin.name().to_string()));
+        }
+        for dependency in plugin.dependencies() {
+            if !self.plugins.contains_key(*dependency) {
+                return Err(PluginError::DependencyMissing {
+                    plugin: plugin.name().to_string(),
+                    dependency: dependency.to_string(),
+                });
+            }
+        }
+
+        plugin.on_load(&config)?;
+        trace!("Plugin '{}' loaded successfully.", plugin.name());
+
+        let namespace = plugin.name().to_string();
+        self.plugins.insert(
+            namespace.clone(),
+            LoadedPlugin {
+                plugin,
+                library: Some(Arc::new(library)),
+                path: Some(PathBuf::from(path)),
+                namespace,
+                config,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Loads several plugins at once, topologically sorting them by their
+    /// declared `dependencies()` so each plugin's `on_load` only runs after
+    /// everything it depends on (whether already loaded, or earlier in this
+    /// same batch) has finished loading.
+    ///
+    /// # Parameters
+    /// - `entries`: `(library_path, config_path)` pairs, one per plugin to load.
+    ///
+    /// # Returns
+    /// - `Ok(())` if every plugin loaded successfully, in dependency order.
+    /// - `Err(PluginError::DependencyMissing)` if a plugin's dependency is
+    ///   neither already loaded nor present in this batch.
+    pub fn load_all(&mut self, entries: &[(&str, Option<&str>)]) -> Result<(), PluginError> {
+        let mut pending: HashMap<String, PendingPlugin> = HashMap::new();
+        for (path, config_path) in entries {
+            let (plugin, library) = unsafe { Self::create_from_library(path)? };
+            if self.plugins.contains_key(plugin.name()) {
+                return Err(PluginError::AlreadyLoaded(plugin.name().to_string()));
+            }
+            let dependencies = plugin
+                .dependencies()
+                .iter()
+                .map(|dep| dep.to_string())
+                .collect();
+            pending.insert(
+                plugin.name().to_string(),
+                PendingPlugin {
+                    plugin,
+                    library,
+                    path,
+                    config_path: *config_path,
+                    dependencies,
+                },
+            );
+        }
+
+        // Loaded plugins are inserted into `self.plugins` as soon as they're
+        // processed, so a dependency is satisfied once it shows up there —
+        // whether it was loaded before this call or earlier in this batch.
+        let dependencies: HashMap<String, Vec<String>> = pending
+            .iter()
+            .map(|(name, p)| (name.clone(), p.dependencies.clone()))
+            .collect();
+        let already_loaded: HashSet<String> = self.plugins.keys().cloned().collect();
+        let order = Self::order_by_dependencies(&dependencies, &already_loaded)?;
+
+        for name in order {
+            let PendingPlugin {
+                mut plugin,
+                library,
+                path,
+                config_path,
+                ..
+            } = pending.remove(&name).unwrap();
+            let config = match config_path {
+                Some(config_path) => PluginConfig::Path(PathBuf::from(config_path)),
+                None => PluginConfig::None,
+            };
+
+            plugin.on_load(&config)?;
+            trace!("Plugin '{}' loaded successfully.", plugin.name());
+
+            self.plugins.insert(
+                name.clone(),
+                LoadedPlugin {
+                    plugin,
+                    library: Some(Arc::new(library)),
+                    path: Some(PathBuf::from(path)),
+                    namespace: name,
+                    config,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Determines a load order for a batch of plugins given their declared
+    /// `dependencies`, without touching any library or Lua state — kept
+    /// separate from `load_all` so the ordering logic can be exercised
+    /// directly in tests.
+    ///
+    /// # Parameters
+    /// - `dependencies`: each pending plugin's name mapped to the names it depends on.
+    /// - `already_loaded`: names that are satisfied up front (loaded before this batch).
+    ///
+    /// # Returns
+    /// - `Ok(order)`: names from `dependencies`, ordered so each entry's
+    ///   dependencies are already `already_loaded` or earlier in `order`.
+    /// - `Err(PluginError::DependencyMissing)`: the first name that can't be
+    ///   ordered, either because a dependency was never provided at all, or
+    ///   because of a cycle (reported as `<cyclic dependency>`).
+    fn order_by_dependencies(
+        dependencies: &HashMap<String, Vec<String>>,
+        already_loaded: &HashSet<String>,
+    ) -> Result<Vec<String>, PluginError> {
+        let mut satisfied = already_loaded.clone();
+        let mut remaining: Vec<String> = dependencies.keys().cloned().collect();
+        let mut order = Vec::with_capacity(remaining.len());
+
+        while !remaining.is_empty() {
+            let ready_index = remaining
+                .iter()
+                .position(|name| dependencies[name].iter().all(|dep| satisfied.contains(dep)));
+
+            let Some(index) = ready_index else {
+                // Nothing in `remaining` has all its dependencies satisfied:
+                // either a genuine cycle, or a dependency that was never
+                // provided at all.
+                let missing = remaining.iter().find_map(|name| {
+                    dependencies[name]
+                        .iter()
+                        .find(|dep| !satisfied.contains(*dep) && !dependencies.contains_key(*dep))
+                        .map(|dep| (name.clone(), dep.clone()))
+                });
+                let (plugin, dependency) = missing.unwrap_or_else(|| {
+                    (remaining[0].clone(), "<cyclic dependency>".to_string())
+                });
+                return Err(PluginError::DependencyMissing { plugin, dependency });
+            };
+
+            let name = remaining.remove(index);
+            satisfied.insert(name.clone());
+            order.push(name);
+        }
+
+        Ok(order)
+    }
+
+    /// Loads a dynamic library, verifies it was built against a compatible
+    /// `PluginLua` ABI, and invokes its exported `create_plugin` function.
+    ///
+    /// Checking the interface and `rustc` version symbols before calling
+    /// `create_plugin` turns a mismatched-ABI segfault into a descriptive
+    /// load error.
+    ///
+    /// # Safety
+    /// This dereferences a raw `*mut dyn PluginLua` vtable handed back by the
+    /// library, which is only sound if the library was built against a
+    /// compatible `PluginLua` ABI.
+    unsafe fn create_from_library(
+        path: &str,
+    ) -> Result<(Box<dyn PluginLua>, Library), PluginError> {
         type PluginCreate = unsafe fn() -> *mut dyn PluginLua;
 
+        let lib = Self::verify_and_load_library(path)?;
+
+        let create_plugin: Symbol<PluginCreate> = lib
+            .get(b"create_plugin")
+            .map_err(|_| PluginError::SymbolMissing("create_plugin".to_string()))?;
+        let boxed_raw_plugin = Box::from_raw(create_plugin());
+
+        Ok((boxed_raw_plugin, lib))
+    }
+
+    /// Loads `path` and checks its `plugin_interface_version` /
+    /// `plugin_rustc_version` symbols against this host's, shared by both
+    /// single-plugin (`create_plugin`) and registrar (`plugin_entry`)
+    /// libraries so an ABI mismatch is caught the same way for either.
+    ///
+    /// # Safety
+    /// Calls into the dynamic library's exported version symbols, which is
+    /// only sound if they were produced by `export_plugin!` /
+    /// `export_plugin_registrar!`.
+    unsafe fn verify_and_load_library(path: &str) -> Result<Library, PluginError> {
+        type PluginInterfaceVersionFn = unsafe fn() -> u32;
+        type PluginRustcVersionFn = unsafe fn() -> *const std::os::raw::c_char;
+
+        let lib = Library::new(path)?;
+        trace!("Library loaded from path: {}", path);
+
+        let interface_version_fn: Symbol<PluginInterfaceVersionFn> = lib
+            .get(b"plugin_interface_version")
+            .map_err(|_| PluginError::SymbolMissing("plugin_interface_version".to_string()))?;
+        let plugin_interface_version = interface_version_fn();
+        if plugin_interface_version != PLUGIN_INTERFACE_VERSION {
+            return Err(PluginError::AbiMismatch(
+                path.to_string(),
+                format!(
+                    "built against interface version {}, but this host expects version {}",
+                    plugin_interface_version, PLUGIN_INTERFACE_VERSION
+                ),
+            ));
+        }
+
+        let rustc_version_fn: Symbol<PluginRustcVersionFn> = lib
+            .get(b"plugin_rustc_version")
+            .map_err(|_| PluginError::SymbolMissing("plugin_rustc_version".to_string()))?;
+        let plugin_rustc_version = std::ffi::CStr::from_ptr(rustc_version_fn())
+            .to_string_lossy()
+            .into_owned();
+        if plugin_rustc_version != RUSTC_VERSION {
+            return Err(PluginError::AbiMismatch(
+                path.to_string(),
+                format!(
+                    "compiled with '{}', but this host was compiled with '{}'",
+                    plugin_rustc_version, RUSTC_VERSION
+                ),
+            ));
+        }
+
+        Ok(lib)
+    }
+
+    /// Loads a registrar-style plugin library: one whose `plugin_entry`
+    /// symbol receives `&mut dyn PluginRegistrar` and can register several
+    /// [`PluginLua`] instances, each under its own Lua namespace, instead of
+    /// the single plugin/namespace pairing `load_plugin` produces.
+    ///
+    /// # Safety
+    /// Invokes the library's exported `plugin_entry` function, which is only
+    /// sound if the library was built with `export_plugin_registrar!` against
+    /// a compatible ABI.
+    pub fn load_registrar_plugin(&mut self, path: &str) -> Result<(), PluginError> {
+        // `&mut dyn PluginRegistrar` is a fat pointer with no defined C
+        // layout, which trips `improper_ctypes_definitions` by default.
+        // That's fine here: this type only ever names the symbol exported by
+        // `export_plugin_registrar!` in the exact same `rustc` build it's
+        // ABI-checked against (see `verify_and_load_library`), so the
+        // Rust-level fat-pointer layout is guaranteed to match on both sides.
+        #[allow(improper_ctypes_definitions)]
+        type PluginEntry = unsafe extern "C" fn(&mut dyn PluginRegistrar);
+
+        let library = unsafe { Self::verify_and_load_library(path)? };
+        let entry_fn: PluginEntry = unsafe {
+            let entry: Symbol<PluginEntry> = library
+                .get(b"plugin_entry")
+                .map_err(|_| PluginError::SymbolMissing("plugin_entry".to_string()))?;
+            *entry
+        };
+
+        self.pending_library = Some(Arc::new(library));
         unsafe {
-            // Load the dynamic library.
-            let lib = Library::new(path)?;
-            trace!("Library loaded from path: {}", path);
+            entry_fn(self);
+        }
+        self.pending_library = None;
+
+        Ok(())
+    }
+
+    /// Starts watching a previously loaded plugin's dynamic library for
+    /// changes on disk, so it can be hot-reloaded during iterative
+    /// Lua-host development without restarting the whole process.
+    ///
+    /// # Parameters
+    /// - `name`: Name of the already-loaded plugin to watch.
+    ///
+    /// # Returns
+    /// - `Ok(())` once the watcher is installed.
+    /// - `Err` if the plugin isn't loaded from a file, or the watcher
+    ///   couldn't be started.
+    pub fn watch_plugin(&mut self, name: &str) -> Result<(), PluginError> {
+        let loaded = self
+            .plugins
+            .get(name)
+            .ok_or_else(|| PluginError::NotFound(name.to_string()))?;
+        let path = loaded
+            .path
+            .clone()
+            .ok_or_else(|| PluginError::NotFound(name.to_string()))?;
+
+        let watcher = PluginWatcher::new(&path, name.to_string(), self.pending_reloads.clone())
+            .map_err(PluginError::Plugin)?;
+        self.watchers.insert(name.to_string(), watcher);
+        trace!(
+            "Watching plugin '{}' at '{}' for changes.",
+            name,
+            path.display()
+        );
+
+        Ok(())
+    }
+
+    /// Applies any reloads queued up by file watchers since the last call.
+    ///
+    /// This must be called from the thread that owns `lua`, since watcher
+    /// callbacks run on a separate filesystem-notification thread and cannot
+    /// safely touch the Lua state themselves.
+    pub fn process_reloads(&mut self, lua: &Lua) -> Result<(), PluginError> {
+        let names: Vec<String> = std::mem::take(&mut *self.pending_reloads.lock().unwrap());
+        for name in names {
+            self.reload_plugin(&name, lua)?;
+        }
+        Ok(())
+    }
+
+    /// Reloads a single plugin from disk: runs `on_unload`, drops the old
+    /// `Library`, reloads the plugin via `create_plugin`, runs `on_load`
+    /// again, and re-registers its Lua functions into the live `Lua` state.
+    ///
+    /// # Parameters
+    /// - `name`: Name of the loaded plugin to reload.
+    /// - `lua`: The live Lua state to re-register the plugin's functions into.
+    pub fn reload_plugin(&mut self, name: &str, lua: &Lua) -> Result<(), PluginError> {
+        let existing = self
+            .plugins
+            .get(name)
+            .ok_or_else(|| PluginError::NotFound(name.to_string()))?;
+        if existing.path.is_none() {
+            return Err(PluginError::NotFound(name.to_string()));
+        }
 
-            // Locate and invoke the plugin's create function.
-            let create_plugin: Symbol<PluginCreate> = lib.get(b"create_plugin")?;
-            let mut boxed_raw_plugin = Box::from_raw(create_plugin());
+        let mut loaded = self.plugins.remove(name).unwrap();
+        let path = loaded.path.clone().unwrap();
+        let config = loaded.config.clone();
 
-            // Initialize the plugin by calling its `on_load` method.
-            boxed_raw_plugin.on_load()?;
-            trace!("Plugin '{}' loaded successfully.", boxed_raw_plugin.name());
+        loaded.plugin.on_unload()?;
+        self.deregister_from_lua(lua, &loaded.namespace)?;
+        drop(loaded); // drops the plugin, then (once no other namespace shares it) its owning `Library`.
+        trace!("Plugin '{}' unloaded for reload.", name);
 
-            self.plugins
-                .insert(boxed_raw_plugin.name().to_string(), boxed_raw_plugin);
-            self.libraries.push(lib);
+        let (mut plugin, library) = unsafe {
+            let path_str = path
+                .to_str()
+                .ok_or_else(|| PluginError::Plugin("plugin path is not valid UTF-8".into()))?;
+            Self::create_from_library(path_str)?
+        };
+        plugin.on_load(&config)?;
+
+        let plugin_table = lua.create_table()?;
+        for (fn_name, function) in plugin.get_lua_functions(lua) {
+            plugin_table.set(fn_name, function)?;
         }
+        lua.globals().set(plugin.name(), plugin_table)?;
+
+        let namespace = plugin.name().to_string();
+        self.plugins.insert(
+            namespace.clone(),
+            LoadedPlugin {
+                plugin,
+                library: Some(Arc::new(library)),
+                path: Some(path),
+                namespace,
+                config,
+            },
+        );
+        trace!("Plugin '{}' reloaded successfully.", name);
 
         Ok(())
     }
@@ -96,14 +562,40 @@ impl PluginManager {
     ///
     /// # Parameters
     /// - `name`: Name of the plugin to be unloaded.
+    /// - `lua`: The live Lua state to remove the plugin's registered global from.
     ///
     /// # Returns
     /// - `Ok(())` if the plugin is successfully unloaded.
-    /// - `Err` if the plugin fails to clean up resources or is not found.
-    pub fn unload_plugin(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
-        if let Some(mut plugin) = self.plugins.remove(name) {
-            // Call `on_unload` to allow the plugin to clean up resources.
-            plugin.on_unload()?;
+    /// - `Err` if the plugin fails to clean up resources or is still depended
+    ///   on by another loaded plugin. Unloading a name that isn't loaded is
+    ///   not an error: it is logged and `Ok(())` is returned.
+    pub fn unload_plugin(&mut self, name: &str, lua: &Lua) -> Result<(), PluginError> {
+        let dependents: Vec<String> = self
+            .plugins
+            .iter()
+            .filter(|(other_name, loaded)| {
+                other_name.as_str() != name && loaded.plugin.dependencies().contains(&name)
+            })
+            .map(|(other_name, _)| other_name.clone())
+            .collect();
+        if !dependents.is_empty() {
+            return Err(PluginError::InUseBy {
+                plugin: name.to_string(),
+                dependents,
+            });
+        }
+
+        self.watchers.remove(name);
+
+        if let Some(mut loaded) = self.plugins.remove(name) {
+            // Call `on_unload` to allow the plugin to clean up resources,
+            // remove its global from the live Lua state so no dangling
+            // function pointer can be called from Lua, and only then drop
+            // its `Library` so code from the plugin stays mapped until it's
+            // truly finished running.
+            loaded.plugin.on_unload()?;
+            self.deregister_from_lua(lua, &loaded.namespace)?;
+            drop(loaded);
             trace!("Plugin '{}' unloaded successfully.", name);
         } else {
             trace!("Plugin '{}' not found during unload.", name);
@@ -121,22 +613,23 @@ impl PluginManager {
     /// - `Some(&dyn PluginLua)` if the plugin is found.
     /// - `None` if the plugin is not loaded.
     pub fn get_plugin(&self, name: &str) -> Option<&dyn PluginLua> {
-        self.plugins.get(name).map(|plugin| plugin.as_ref())
+        self.plugins.get(name).map(|loaded| loaded.plugin.as_ref())
     }
 
     /// Registers a plugin instance directly, bypassing file loading.
     ///
     /// # Parameters
     /// - `plugin`: A boxed instance of a plugin implementing the `PluginLua` trait.
+    /// - `config`: Configuration to hand to the plugin's `on_load`.
     ///
     /// # Returns
     /// - `Ok(())` if the plugin was successfully registered.
-    /// - `Err(Box<dyn Error>)` if an error occurs during plugin initialization.
+    /// - `Err(PluginError)` if an error occurs during plugin initialization.
     ///
     /// # Example
     /// ```rust
     /// let plugin: Box<dyn PluginLua> = Box::new(MyPlugin::new());
-    /// plugin_manager.register_plugin_instance(plugin)?;
+    /// plugin_manager.register_plugin_instance(plugin, PluginConfig::None)?;
     /// ```
     ///
     /// # Notes
@@ -145,10 +638,20 @@ impl PluginManager {
     pub fn register_plugin_instance(
         &mut self,
         mut plugin: Box<dyn PluginLua>,
-    ) -> Result<(), Box<dyn Error>> {
-        let plugin_name = plugin.name().to_string();
-        plugin.on_load()?; // Initialize the plugin
-        self.plugins.insert(plugin_name, plugin);
+        config: PluginConfig,
+    ) -> Result<(), PluginError> {
+        plugin.on_load(&config)?; // Initialize the plugin
+        let namespace = plugin.name().to_string();
+        self.plugins.insert(
+            namespace.clone(),
+            LoadedPlugin {
+                plugin,
+                library: None,
+                path: None,
+                namespace,
+                config,
+            },
+        );
         Ok(())
     }
 
@@ -160,14 +663,23 @@ impl PluginManager {
     /// # Returns
     /// - `Ok(())` if all functions are registered successfully.
     /// - `Err` if there is an error during registration.
-    pub fn register_all_plugins(&self, lua: &Lua) -> Result<(), Box<dyn Error>> {
-        for plugin in self.plugins.values() {
-            trace!("Registering functions for plugin '{}'.", plugin.name());
+    pub fn register_all_plugins(&self, lua: &Lua) -> Result<(), PluginError> {
+        // Register parents before their dotted children (`"net"` before
+        // `"net.http"`), so setting a parent table can't replace a child's.
+        let mut loaded_plugins: Vec<&LoadedPlugin> = self.plugins.values().collect();
+        loaded_plugins.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+        for loaded in loaded_plugins {
+            let plugin = &loaded.plugin;
+            trace!(
+                "Registering functions for plugin '{}' under '{}'.",
+                plugin.name(),
+                loaded.namespace
+            );
             let plugin_table = lua.create_table()?;
             for (name, function) in plugin.get_lua_functions(lua) {
                 plugin_table.set(name, function)?;
             }
-            lua.globals().set(plugin.name(), plugin_table)?;
+            Self::set_namespaced_global(lua, &loaded.namespace, plugin_table)?;
             trace!(
                 "Functions for plugin '{}' registered successfully.",
                 plugin.name()
@@ -175,14 +687,145 @@ impl PluginManager {
         }
         Ok(())
     }
+
+    /// Returns the commands exposed by all currently loaded plugins, for a
+    /// host to build a command listing or dispatch table from.
+    pub fn list_commands(&self) -> Vec<CommandDesc> {
+        self.plugins
+            .values()
+            .flat_map(|loaded| loaded.plugin.commands())
+            .collect()
+    }
+
+    /// Injects a `help(name)` Lua global that looks up a command's `help` and
+    /// `usage` text by name across all loaded plugins, so a host can build a
+    /// `/help` command without every plugin reinventing its own
+    /// introspection. Returns `nil` for an unknown command name.
+    ///
+    /// The command list is snapshotted at call time, not looked up live: a
+    /// plugin loaded, reloaded, or unloaded afterwards won't be reflected in
+    /// `help()` until this is called again. Call it again after any topology
+    /// change (`load_plugin`, `load_all`, `reload_plugin`, `unload_plugin`)
+    /// that should be visible to it.
+    ///
+    /// # Parameters
+    /// - `lua`: The Lua state to inject `help` into.
+    pub fn register_command_table(&self, lua: &Lua) -> Result<(), PluginError> {
+        let commands = self.list_commands();
+        let help_fn = lua.create_function(move |lua, name: String| {
+            match commands.iter().find(|cmd| cmd.name == name) {
+                Some(cmd) => {
+                    let table = lua.create_table()?;
+                    table.set("name", cmd.name.clone())?;
+                    table.set("help", cmd.help.clone())?;
+                    table.set("usage", cmd.usage.clone())?;
+                    Ok(mlua::Value::Table(table))
+                }
+                None => Ok(mlua::Value::Nil),
+            }
+        })?;
+        lua.globals().set("help", help_fn)?;
+        Ok(())
+    }
+
+    /// Removes a plugin's registered global in lockstep with unloading it, so
+    /// code from an unloaded plugin can't dangle as a stale Lua function
+    /// pointer. Safe to call even if `namespace` was never registered.
+    ///
+    /// # Parameters
+    /// - `lua`: The Lua state to remove the global from.
+    /// - `namespace`: The dotted namespace the plugin was registered under.
+    pub fn deregister_from_lua(&self, lua: &Lua, namespace: &str) -> Result<(), PluginError> {
+        Self::set_namespaced_global(lua, namespace, mlua::Nil)?;
+        Ok(())
+    }
+
+    /// Sets `value` as a Lua global reachable via `namespace`, creating
+    /// intermediate tables for any `.`-separated segments along the way
+    /// (e.g. `"net.http"` becomes `net.http` with `net` auto-vivified).
+    /// Passing `mlua::Nil` removes the global instead.
+    fn set_namespaced_global<'lua>(
+        lua: &'lua Lua,
+        namespace: &str,
+        value: impl mlua::IntoLua<'lua>,
+    ) -> mlua::Result<()> {
+        let mut segments = namespace.split('.');
+        let Some(mut current_name) = segments.next() else {
+            return Ok(());
+        };
+
+        let globals = lua.globals();
+        let mut current_table: Option<mlua::Table> = None;
+
+        for next_name in segments {
+            let parent = current_table.clone().unwrap_or_else(|| globals.clone());
+            let child: mlua::Table = match parent.get(current_name)? {
+                Some(table) => table,
+                None => {
+                    let table = lua.create_table()?;
+                    parent.set(current_name, table.clone())?;
+                    table
+                }
+            };
+            current_table = Some(child);
+            current_name = next_name;
+        }
+
+        match current_table {
+            Some(parent) => parent.set(current_name, value),
+            None => globals.set(current_name, value),
+        }
+    }
+}
+
+impl PluginRegistrar for PluginManager {
+    fn register(
+        &mut self,
+        namespace: &str,
+        mut plugin: Box<dyn PluginLua>,
+        config: PluginConfig,
+    ) -> Result<(), PluginError> {
+        if self.plugins.contains_key(namespace) {
+            return Err(PluginError::AlreadyLoaded(namespace.to_string()));
+        }
+        for dependency in plugin.dependencies() {
+            if !self.plugins.contains_key(*dependency) {
+                return Err(PluginError::DependencyMissing {
+                    plugin: plugin.name().to_string(),
+                    dependency: dependency.to_string(),
+                });
+            }
+        }
+
+        plugin.on_load(&config)?;
+        trace!(
+            "Plugin '{}' registered under namespace '{}'.",
+            plugin.name(),
+            namespace
+        );
+
+        self.plugins.insert(
+            namespace.to_string(),
+            LoadedPlugin {
+                plugin,
+                library: self.pending_library.clone(),
+                path: None,
+                namespace: namespace.to_string(),
+                config,
+            },
+        );
+
+        Ok(())
+    }
 }
 
 impl Drop for PluginManager {
     /// Ensures that all plugins are unloaded and cleaned up when the `PluginManager` is dropped.
     fn drop(&mut self) {
-        for (_, mut plugin) in self.plugins.drain() {
-            // Call `on_unload` for proper cleanup before unloading.
-            if let Err(e) = plugin.on_unload() {
+        for (_, mut loaded) in self.plugins.drain() {
+            // Call `on_unload` for proper cleanup before unloading, then let
+            // `loaded` (and its owning `Library`) drop at the end of scope.
+            if let Err(e) = loaded.plugin.on_unload() {
                 error!("Error unloading plugin: {}", e);
             }
         }
@@ -193,7 +836,10 @@ impl Drop for PluginManager {
 /// Macro to export the plugin's create function.
 ///
 /// This macro defines the `create_plugin` function that is used to instantiate the plugin
-/// from a dynamic library.
+/// from a dynamic library, along with the `plugin_interface_version` and
+/// `plugin_rustc_version` symbols the host checks before calling it, so a
+/// plugin built against an incompatible ABI fails to load with a clear error
+/// instead of segfaulting.
 ///
 /// # Example
 /// ```rust
@@ -203,9 +849,304 @@ impl Drop for PluginManager {
 macro_rules! export_plugin {
     ($plugin_type:ty) => {
         #[no_mangle]
-        pub extern "C" fn create_plugin() -> *mut dyn PluginLua {
+        pub extern "C" fn create_plugin() -> *mut dyn $crate::PluginLua {
             let plugin = <$plugin_type>::new();
             Box::into_raw(Box::new(plugin))
         }
+
+        $crate::export_plugin_abi_symbols!();
+    };
+}
+
+/// Macro to export the `plugin_interface_version` and `plugin_rustc_version`
+/// symbols shared by `export_plugin!` and `export_plugin_registrar!`.
+///
+/// Not meant to be used directly by plugin authors.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! export_plugin_abi_symbols {
+    () => {
+        #[no_mangle]
+        pub extern "C" fn plugin_interface_version() -> u32 {
+            $crate::PLUGIN_INTERFACE_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn plugin_rustc_version() -> *const std::os::raw::c_char {
+            static VERSION: std::sync::OnceLock<std::ffi::CString> = std::sync::OnceLock::new();
+            VERSION
+                .get_or_init(|| std::ffi::CString::new($crate::RUSTC_VERSION).unwrap())
+                .as_ptr()
+        }
+    };
+}
+
+/// Macro to export a registrar-style plugin entry point.
+///
+/// Defines the `plugin_entry` function that `PluginManager::load_registrar_plugin`
+/// calls with `&mut dyn PluginRegistrar`, letting `$entry_fn` register several
+/// [`PluginLua`] instances from a single dynamic library, each under its own
+/// Lua namespace. Also emits the same `plugin_interface_version` and
+/// `plugin_rustc_version` symbols as `export_plugin!`.
+///
+/// # Example
+/// ```rust
+/// fn setup(registrar: &mut dyn PluginRegistrar) {
+///     registrar.register("net", Box::new(NetPlugin::new()), PluginConfig::None).unwrap();
+///     registrar.register("net.http", Box::new(HttpPlugin::new()), PluginConfig::None).unwrap();
+/// }
+///
+/// export_plugin_registrar!(setup);
+/// ```
+#[macro_export]
+macro_rules! export_plugin_registrar {
+    ($entry_fn:path) => {
+        #[no_mangle]
+        // `&mut dyn PluginRegistrar` is a fat pointer with no defined C
+        // layout, which trips `improper_ctypes_definitions` by default.
+        // That's fine here: `plugin_entry` is never called across a real C
+        // boundary, only by `PluginManager::load_registrar_plugin` in the
+        // exact same `rustc` build it's ABI-checked against (see
+        // `verify_and_load_library`), so the Rust-level fat-pointer layout is
+        // guaranteed to match on both sides.
+        #[allow(improper_ctypes_definitions)]
+        pub extern "C" fn plugin_entry(registrar: &mut dyn $crate::PluginRegistrar) {
+            $entry_fn(registrar);
+        }
+
+        $crate::export_plugin_abi_symbols!();
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn const_parse_u32_parses_decimal_digits() {
+        assert_eq!(const_parse_u32("0"), 0);
+        assert_eq!(const_parse_u32("7"), 7);
+        assert_eq!(const_parse_u32("42"), 42);
+        assert_eq!(const_parse_u32("123"), 123);
+    }
+
+    struct StubPlugin {
+        commands: Vec<CommandDesc>,
+        dependencies: &'static [&'static str],
+    }
+
+    impl PluginLua for StubPlugin {
+        fn name(&self) -> &str {
+            "stub"
+        }
+
+        fn on_load(&mut self, _config: &PluginConfig) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn on_unload(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn get_lua_functions(&self, _lua: &Lua) -> HashMap<String, mlua::Function> {
+            HashMap::new()
+        }
+
+        fn commands(&self) -> Vec<CommandDesc> {
+            self.commands.clone()
+        }
+
+        fn dependencies(&self) -> &[&str] {
+            self.dependencies
+        }
+    }
+
+    #[test]
+    fn list_commands_aggregates_across_loaded_plugins() {
+        let mut manager = PluginManager::new();
+        manager
+            .register_plugin_instance(
+                Box::new(StubPlugin {
+                    commands: vec![CommandDesc {
+                        name: "reload".to_string(),
+                        help: "Reloads a plugin".to_string(),
+                        usage: "reload <plugin>".to_string(),
+                    }],
+                    dependencies: &[],
+                }),
+                PluginConfig::None,
+            )
+            .unwrap();
+
+        let commands = manager.list_commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].name, "reload");
+    }
+
+    #[test]
+    fn register_command_table_exposes_help_by_name() {
+        let mut manager = PluginManager::new();
+        manager
+            .register_plugin_instance(
+                Box::new(StubPlugin {
+                    commands: vec![CommandDesc {
+                        name: "reload".to_string(),
+                        help: "Reloads a plugin".to_string(),
+                        usage: "reload <plugin>".to_string(),
+                    }],
+                    dependencies: &[],
+                }),
+                PluginConfig::None,
+            )
+            .unwrap();
+
+        let lua = Lua::new();
+        manager.register_command_table(&lua).unwrap();
+
+        let help: mlua::Function = lua.globals().get("help").unwrap();
+        let found: mlua::Table = help.call("reload").unwrap();
+        let usage: String = found.get("usage").unwrap();
+        assert_eq!(usage, "reload <plugin>");
+
+        let missing: mlua::Value = help.call("does-not-exist").unwrap();
+        assert!(matches!(missing, mlua::Value::Nil));
+    }
+
+    #[test]
+    fn register_rejects_a_namespace_that_is_already_taken() {
+        let mut manager = PluginManager::new();
+        manager
+            .register(
+                "net",
+                Box::new(StubPlugin {
+                    commands: Vec::new(),
+                    dependencies: &[],
+                }),
+                PluginConfig::None,
+            )
+            .unwrap();
+
+        let err = manager
+            .register(
+                "net",
+                Box::new(StubPlugin {
+                    commands: Vec::new(),
+                    dependencies: &[],
+                }),
+                PluginConfig::None,
+            )
+            .unwrap_err();
+
+        assert!(matches!(err, PluginError::AlreadyLoaded(name) if name == "net"));
+        assert_eq!(manager.plugins.len(), 1);
+    }
+
+    #[test]
+    fn register_rejects_a_plugin_whose_dependency_is_not_loaded() {
+        let mut manager = PluginManager::new();
+        let err = manager
+            .register(
+                "net.http",
+                Box::new(StubPlugin {
+                    commands: Vec::new(),
+                    dependencies: &["net"],
+                }),
+                PluginConfig::None,
+            )
+            .unwrap_err();
+
+        match err {
+            PluginError::DependencyMissing { plugin, dependency } => {
+                assert_eq!(plugin, "stub");
+                assert_eq!(dependency, "net");
+            }
+            other => panic!("expected DependencyMissing, got {other:?}"),
+        }
+        assert!(manager.plugins.is_empty());
+    }
+
+    #[test]
+    fn set_namespaced_global_auto_vivifies_dotted_segments() {
+        let lua = Lua::new();
+        PluginManager::set_namespaced_global(&lua, "net.http", "http_plugin").unwrap();
+
+        let net: mlua::Table = lua.globals().get("net").unwrap();
+        let value: String = net.get("http").unwrap();
+        assert_eq!(value, "http_plugin");
+    }
+
+    #[test]
+    fn set_namespaced_global_single_segment_is_a_plain_global() {
+        let lua = Lua::new();
+        PluginManager::set_namespaced_global(&lua, "net", "net_plugin").unwrap();
+
+        let value: String = lua.globals().get("net").unwrap();
+        assert_eq!(value, "net_plugin");
+    }
+
+    #[test]
+    fn set_namespaced_global_nil_removes_the_global() {
+        let lua = Lua::new();
+        PluginManager::set_namespaced_global(&lua, "net.http", "http_plugin").unwrap();
+        PluginManager::set_namespaced_global(&lua, "net.http", mlua::Nil).unwrap();
+
+        let net: mlua::Table = lua.globals().get("net").unwrap();
+        let value: mlua::Value = net.get("http").unwrap();
+        assert!(matches!(value, mlua::Value::Nil));
+    }
+
+    fn deps(pairs: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(name, deps)| {
+                (
+                    name.to_string(),
+                    deps.iter().map(|dep| dep.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn order_by_dependencies_orders_by_declared_deps() {
+        let dependencies = deps(&[("b", &["a"]), ("a", &[]), ("c", &["a", "b"])]);
+        let order = PluginManager::order_by_dependencies(&dependencies, &HashSet::new()).unwrap();
+
+        let index_of = |name: &str| order.iter().position(|n| n == name).unwrap();
+        assert!(index_of("a") < index_of("b"));
+        assert!(index_of("b") < index_of("c"));
+    }
+
+    #[test]
+    fn order_by_dependencies_is_satisfied_by_already_loaded() {
+        let dependencies = deps(&[("b", &["a"])]);
+        let already_loaded: HashSet<String> = ["a".to_string()].into_iter().collect();
+        let order = PluginManager::order_by_dependencies(&dependencies, &already_loaded).unwrap();
+        assert_eq!(order, vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn order_by_dependencies_reports_missing_dependency() {
+        let dependencies = deps(&[("b", &["a"])]);
+        let err = PluginManager::order_by_dependencies(&dependencies, &HashSet::new()).unwrap_err();
+        match err {
+            PluginError::DependencyMissing { plugin, dependency } => {
+                assert_eq!(plugin, "b");
+                assert_eq!(dependency, "a");
+            }
+            other => panic!("expected DependencyMissing, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn order_by_dependencies_reports_cycle() {
+        let dependencies = deps(&[("a", &["b"]), ("b", &["a"])]);
+        let err = PluginManager::order_by_dependencies(&dependencies, &HashSet::new()).unwrap_err();
+        match err {
+            PluginError::DependencyMissing { dependency, .. } => {
+                assert_eq!(dependency, "<cyclic dependency>");
+            }
+            other => panic!("expected DependencyMissing, got {other:?}"),
+        }
+    }
+}
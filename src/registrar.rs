@@ -0,0 +1,21 @@
+use crate::{PluginConfig, PluginError, PluginLua};
+
+/// Lets a single dynamic library register several [`PluginLua`] instances at
+/// once, each under its own Lua namespace, instead of being limited to the
+/// one plugin/one namespace pairing `export_plugin!` produces.
+///
+/// A library built around this trait exports a `plugin_entry` function (see
+/// `export_plugin_registrar!`) that receives `&mut dyn PluginRegistrar` and
+/// calls `register` once per plugin it wants to expose, e.g. a `net` module
+/// registering itself as `"net"` plus a sibling `"net.http"` and `"net.ws"`.
+pub trait PluginRegistrar {
+    /// Registers `plugin` under `namespace` (dotted namespaces like
+    /// `"net.http"` land in a nested Lua table), initializing it with
+    /// `config`.
+    fn register(
+        &mut self,
+        namespace: &str,
+        plugin: Box<dyn PluginLua>,
+        config: PluginConfig,
+    ) -> Result<(), PluginError>;
+}
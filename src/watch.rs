@@ -0,0 +1,37 @@
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{trace, warn};
+
+/// Watches a single plugin's dynamic library for changes and records its name
+/// for pickup by [`crate::PluginManager::process_reloads`].
+///
+/// The watcher itself must be kept alive for as long as the plugin should be
+/// watched; `PluginManager` holds onto it for that reason.
+pub struct PluginWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl PluginWatcher {
+    /// Starts watching `path` for changes, pushing `plugin_name` onto `pending`
+    /// whenever the file is modified.
+    pub fn new(
+        path: &Path,
+        plugin_name: String,
+        pending: Arc<Mutex<Vec<String>>>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                Ok(event) if event.kind.is_modify() => {
+                    trace!("Detected change for plugin '{}'.", plugin_name);
+                    pending.lock().unwrap().push(plugin_name.clone());
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Plugin file watcher error: {}", e),
+            })?;
+
+        watcher.watch(path, RecursiveMode::NonRecursive)?;
+
+        Ok(PluginWatcher { _watcher: watcher })
+    }
+}